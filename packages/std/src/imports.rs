@@ -69,6 +69,20 @@ extern "C" {
     /// greater than 1 in case of error.
     fn ed25519_batch_verify(messages_ptr: u32, signatures_ptr: u32, public_keys_ptr: u32) -> u32;
 
+    /// Verifies a message against a signature with a public key, using the
+    /// sr25519 Schnorrkel scheme. `message_ptr` points to the full signing
+    /// transcript bytes (i.e. the signing context together with the actual
+    /// message), not just the raw message.
+    /// Returns 0 on verification success, 1 on verification failure, and values
+    /// greater than 1 in case of error.
+    fn sr25519_verify(message_ptr: u32, signature_ptr: u32, public_key_ptr: u32) -> u32;
+
+    /// Verifies a batch of messages against a batch of signatures and public keys, using the
+    /// sr25519 Schnorrkel scheme.
+    /// Returns 0 on verification success, 1 on verification failure, and values
+    /// greater than 1 in case of error.
+    fn sr25519_batch_verify(messages_ptr: u32, signatures_ptr: u32, public_keys_ptr: u32) -> u32;
+
     /// Writes a debug message (UFT-8 encoded) to the host for debugging purposes.
     /// The host is free to log or process this in any way it considers appropriate.
     /// In production environments it is expected that those messages are discarded.
@@ -85,6 +99,23 @@ extern "C" {
     fn check_gas() -> u64;
 
     fn gas_evaporate(evaporate: u32) -> u32;
+
+    /// Computes the sha2-256 digest of `data`, returning a pointer to a newly
+    /// allocated Region holding the 32-byte digest. Offloading this to the host
+    /// avoids paying Wasm gas for hashing in the guest.
+    #[cfg(feature = "crypto-hashes")]
+    fn sha256(data_ptr: u32) -> u32;
+
+    /// Computes the keccak-256 digest of `data`, returning a pointer to a newly
+    /// allocated Region holding the 32-byte digest.
+    #[cfg(feature = "crypto-hashes")]
+    fn keccak256(data_ptr: u32) -> u32;
+
+    /// Computes the blake2b digest of `data` with the requested output `length`
+    /// (in bytes), returning a pointer to a newly allocated Region holding the
+    /// digest.
+    #[cfg(feature = "crypto-hashes")]
+    fn blake2b(data_ptr: u32, length: u32) -> u32;
 }
 
 /// A stateless convenience wrapper around database imports provided by the VM.
@@ -176,6 +207,183 @@ impl Iterator for ExternalIterator {
     }
 }
 
+/// Prepends the length of `namespace` (as a 2-byte big endian integer) to `namespace` itself,
+/// so namespaces of different lengths can never collide as key prefixes (e.g. `b"a"` and
+/// `b"ab"` would otherwise both prefix the key `b"b"`).
+///
+/// Panics if `namespace` is longer than `u16::MAX` bytes, since its length would silently
+/// truncate when cast to `u16` and produce a wrong (and possibly colliding) prefix rather than
+/// rejecting the namespace outright.
+fn to_length_prefixed(namespace: &[u8]) -> Vec<u8> {
+    assert!(
+        namespace.len() <= usize::from(u16::MAX),
+        "only namespaces up to {} bytes are supported",
+        u16::MAX
+    );
+    let mut out = Vec::with_capacity(2 + namespace.len());
+    out.extend_from_slice(&(namespace.len() as u16).to_be_bytes());
+    out.extend_from_slice(namespace);
+    out
+}
+
+fn concat(namespace: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(namespace.len() + key.len());
+    out.extend_from_slice(namespace);
+    out.extend_from_slice(key);
+    out
+}
+
+/// Returns the exclusive upper bound of the key range covered by `namespace`, i.e. the smallest
+/// key that is guaranteed to no longer start with `namespace`. Returns `None` if `namespace` is
+/// empty or consists entirely of `0xff` bytes, in which case there is no such bound short of the
+/// end of the keyspace.
+#[cfg(feature = "iterator")]
+fn namespace_upper_bound(namespace: &[u8]) -> Option<Vec<u8>> {
+    let mut end = namespace.to_vec();
+    for i in (0..end.len()).rev() {
+        if end[i] < 0xff {
+            end[i] += 1;
+            end.truncate(i + 1);
+            return Some(end);
+        }
+    }
+    None
+}
+
+/// A wrapper around a [`Storage`] that transparently namespaces every key with a length-prefixed
+/// byte prefix, so independent sub-modules of a contract can share `deps.storage` without their
+/// keys colliding. This mirrors the "child storage" subtrees Substrate's runtime I/O exposes.
+pub struct PrefixedStorage<'a> {
+    storage: &'a mut dyn Storage,
+    prefix: Vec<u8>,
+}
+
+impl<'a> PrefixedStorage<'a> {
+    pub fn new(storage: &'a mut dyn Storage, namespace: &[u8]) -> Self {
+        PrefixedStorage {
+            storage,
+            prefix: to_length_prefixed(namespace),
+        }
+    }
+
+    /// Wipes every key in this namespace, giving contracts the same subtree-clear semantics
+    /// Substrate's child storage provides for `kill_storage`.
+    #[cfg(feature = "iterator")]
+    pub fn clear_prefix(&mut self) {
+        let start = self.prefix.clone();
+        let end = namespace_upper_bound(&self.prefix);
+        // Collect first: `storage` can't be borrowed immutably (for the range) and mutably
+        // (for remove) at the same time.
+        let keys: Vec<Vec<u8>> = self
+            .storage
+            .range(Some(&start), end.as_deref(), Order::Ascending)
+            .map(|(key, _)| key)
+            .collect();
+        for key in keys {
+            self.storage.remove(&key);
+        }
+    }
+}
+
+impl<'a> Storage for PrefixedStorage<'a> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let full_key = concat(&self.prefix, key);
+        self.storage.get(&full_key)
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        let full_key = concat(&self.prefix, key);
+        self.storage.set(&full_key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        let full_key = concat(&self.prefix, key);
+        self.storage.remove(&full_key);
+    }
+
+    #[cfg(feature = "iterator")]
+    fn range(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let full_start = match start {
+            Some(start) => concat(&self.prefix, start),
+            None => self.prefix.clone(),
+        };
+        let full_end = match end {
+            Some(end) => Some(concat(&self.prefix, end)),
+            None => namespace_upper_bound(&self.prefix),
+        };
+        let prefix_len = self.prefix.len();
+        let iter = self
+            .storage
+            .range(Some(&full_start), full_end.as_deref(), order)
+            .map(move |(key, value)| (key[prefix_len..].to_vec(), value));
+        Box::new(iter)
+    }
+}
+
+/// A read-only counterpart to [`PrefixedStorage`] for callers that only need to read a
+/// namespaced subtree (e.g. queries, which never receive a mutable `Storage`).
+pub struct ReadonlyPrefixedStorage<'a> {
+    storage: &'a dyn Storage,
+    prefix: Vec<u8>,
+}
+
+impl<'a> ReadonlyPrefixedStorage<'a> {
+    pub fn new(storage: &'a dyn Storage, namespace: &[u8]) -> Self {
+        ReadonlyPrefixedStorage {
+            storage,
+            prefix: to_length_prefixed(namespace),
+        }
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        let full_key = concat(&self.prefix, key);
+        self.storage.get(&full_key)
+    }
+
+    #[cfg(feature = "iterator")]
+    pub fn range(
+        &self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record>> {
+        let full_start = match start {
+            Some(start) => concat(&self.prefix, start),
+            None => self.prefix.clone(),
+        };
+        let full_end = match end {
+            Some(end) => Some(concat(&self.prefix, end)),
+            None => namespace_upper_bound(&self.prefix),
+        };
+        let prefix_len = self.prefix.len();
+        let iter = self
+            .storage
+            .range(Some(&full_start), full_end.as_deref(), order)
+            .map(move |(key, value)| (key[prefix_len..].to_vec(), value));
+        Box::new(iter)
+    }
+}
+
+/// Splits a 65-byte Ethereum-style `r || s || v` signature into its 64-byte signature and a
+/// normalized recovery param (`0..=3`), accepting `v` as either `0`/`1` or `27`/`28`.
+fn split_rsv_signature(rsv_signature: &[u8]) -> Result<(&[u8], u8), RecoverPubkeyError> {
+    if rsv_signature.len() != 65 {
+        return Err(RecoverPubkeyError::InvalidSignatureFormat);
+    }
+    let (signature, recovery_byte) = rsv_signature.split_at(64);
+    let v = recovery_byte[0];
+    let recover_param = if v > 26 { v - 27 } else { v };
+    if recover_param > 3 {
+        return Err(RecoverPubkeyError::InvalidRecoveryParam);
+    }
+    Ok((signature, recover_param))
+}
+
 /// A stateless convenience wrapper around imports provided by the VM
 #[derive(Copy, Clone)]
 pub struct ExternalApi {}
@@ -184,6 +392,138 @@ impl ExternalApi {
     pub fn new() -> ExternalApi {
         ExternalApi {}
     }
+
+    /// Recovers a public key from an Ethereum-style 65-byte `r || s || v` signature, as commonly
+    /// produced by `eth_sign`/`ecrecover` call sites. `rsv_signature` must be exactly 65 bytes:
+    /// the 64-byte secp256k1 signature followed by the recovery byte `v`. `v` may be given either
+    /// as `0`/`1` or as `27`/`28` (the Ethereum convention); in the latter case it is normalized
+    /// by subtracting 27 before being passed on to [`Api::secp256k1_recover_pubkey`]. EIP-155
+    /// chain-id-offset `v` values are not normalized here and must be reduced to `0`/`1`/`27`/`28`
+    /// by the caller first. The returned public key is the same 65-byte uncompressed,
+    /// `0x04`-prefixed encoding produced by [`Api::secp256k1_recover_pubkey`]; use
+    /// [`Self::secp256k1_recover_pubkey_rsv_ethereum_address`] if you need the 64-byte
+    /// form Ethereum address derivation expects instead.
+    pub fn secp256k1_recover_pubkey_rsv(
+        &self,
+        message_hash: &[u8],
+        rsv_signature: &[u8],
+    ) -> Result<Vec<u8>, RecoverPubkeyError> {
+        let (signature, recover_param) = split_rsv_signature(rsv_signature)?;
+        self.secp256k1_recover_pubkey(message_hash, signature, recover_param)
+    }
+
+    /// Like [`Self::secp256k1_recover_pubkey_rsv`], but strips the leading `0x04` prefix from the
+    /// recovered key, returning it in the raw 64-byte `x || y` form Ethereum address derivation
+    /// (`keccak256(pubkey)[12..]`) expects.
+    pub fn secp256k1_recover_pubkey_rsv_ethereum_address(
+        &self,
+        message_hash: &[u8],
+        rsv_signature: &[u8],
+    ) -> Result<Vec<u8>, RecoverPubkeyError> {
+        let (signature, recover_param) = split_rsv_signature(rsv_signature)?;
+        let pubkey = self.secp256k1_recover_pubkey(message_hash, signature, recover_param)?;
+        Ok(pubkey[1..].to_vec())
+    }
+
+    /// Verifies a message against a signature with a public key, using the
+    /// sr25519 Schnorrkel scheme. `message` is expected to be the full
+    /// signing transcript bytes (signing context plus message), since
+    /// sr25519 signatures are defined over a configurable transcript rather
+    /// than the raw message bytes alone.
+    pub fn sr25519_verify(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<bool, VerificationError> {
+        let msg_send = build_region(message);
+        let msg_send_ptr = &*msg_send as *const Region as u32;
+        let sig_send = build_region(signature);
+        let sig_send_ptr = &*sig_send as *const Region as u32;
+        let pubkey_send = build_region(public_key);
+        let pubkey_send_ptr = &*pubkey_send as *const Region as u32;
+
+        let result = unsafe { sr25519_verify(msg_send_ptr, sig_send_ptr, pubkey_send_ptr) };
+        match result {
+            0 => Ok(true),
+            1 => Ok(false),
+            2 => panic!("Error code 2 unused since CosmWasm 0.15. This is a bug in the VM."),
+            3 => panic!("InvalidHashFormat must not happen. This is a bug in the VM."),
+            4 => Err(VerificationError::InvalidSignatureFormat),
+            5 => Err(VerificationError::InvalidPubkeyFormat),
+            10 => Err(VerificationError::GenericErr),
+            error_code => Err(VerificationError::unknown_err(error_code)),
+        }
+    }
+
+    /// Verifies a batch of messages against a batch of signatures and public keys, using the
+    /// sr25519 Schnorrkel scheme. Each entry in `messages` is expected to be the full signing
+    /// transcript bytes for that signature, not just the raw message.
+    pub fn sr25519_batch_verify(
+        &self,
+        messages: &[&[u8]],
+        signatures: &[&[u8]],
+        public_keys: &[&[u8]],
+    ) -> Result<bool, VerificationError> {
+        let msgs_encoded = encode_sections(messages);
+        let msgs_send = build_region(&msgs_encoded);
+        let msgs_send_ptr = &*msgs_send as *const Region as u32;
+
+        let sigs_encoded = encode_sections(signatures);
+        let sig_sends = build_region(&sigs_encoded);
+        let sigs_send_ptr = &*sig_sends as *const Region as u32;
+
+        let pubkeys_encoded = encode_sections(public_keys);
+        let pubkeys_send = build_region(&pubkeys_encoded);
+        let pubkeys_send_ptr = &*pubkeys_send as *const Region as u32;
+
+        let result =
+            unsafe { sr25519_batch_verify(msgs_send_ptr, sigs_send_ptr, pubkeys_send_ptr) };
+        match result {
+            0 => Ok(true),
+            1 => Ok(false),
+            2 => panic!("Error code 2 unused since CosmWasm 0.15. This is a bug in the VM."),
+            3 => panic!("InvalidHashFormat must not happen. This is a bug in the VM."),
+            4 => Err(VerificationError::InvalidSignatureFormat),
+            5 => Err(VerificationError::InvalidPubkeyFormat),
+            10 => Err(VerificationError::GenericErr),
+            error_code => Err(VerificationError::unknown_err(error_code)),
+        }
+    }
+
+    /// Computes the sha2-256 digest of `data` using the host's native hasher
+    /// instead of computing it in Wasm bytecode.
+    #[cfg(feature = "crypto-hashes")]
+    pub fn sha256(&self, data: &[u8]) -> Vec<u8> {
+        let data_send = build_region(data);
+        let data_send_ptr = &*data_send as *const Region as u32;
+
+        let result_ptr = unsafe { sha256(data_send_ptr) };
+        unsafe { consume_region(result_ptr as *mut Region) }
+    }
+
+    /// Computes the keccak-256 digest of `data` using the host's native hasher
+    /// instead of computing it in Wasm bytecode.
+    #[cfg(feature = "crypto-hashes")]
+    pub fn keccak256(&self, data: &[u8]) -> Vec<u8> {
+        let data_send = build_region(data);
+        let data_send_ptr = &*data_send as *const Region as u32;
+
+        let result_ptr = unsafe { keccak256(data_send_ptr) };
+        unsafe { consume_region(result_ptr as *mut Region) }
+    }
+
+    /// Computes the blake2b digest of `data` with the given output `length`
+    /// (in bytes) using the host's native hasher instead of computing it in
+    /// Wasm bytecode.
+    #[cfg(feature = "crypto-hashes")]
+    pub fn blake2b(&self, data: &[u8], length: u32) -> Vec<u8> {
+        let data_send = build_region(data);
+        let data_send_ptr = &*data_send as *const Region as u32;
+
+        let result_ptr = unsafe { blake2b(data_send_ptr, length) };
+        unsafe { consume_region(result_ptr as *mut Region) }
+    }
 }
 
 impl Api for ExternalApi {
@@ -434,6 +774,73 @@ impl Api for ExternalApi {
     }
 }
 
+/// Accumulates signature checks of (possibly mixed) schemes and resolves them all at once.
+///
+/// Checks are staged with [`Self::queue_ed25519`] / [`Self::queue_secp256k1`] and resolved with
+/// [`Self::verify_all`]. Queued ed25519 checks are grouped and dispatched through a single
+/// `ed25519_batch_verify` call; there is no batch import for secp256k1 yet, so queued secp256k1
+/// checks are still verified one at a time through `secp256k1_verify`.
+#[derive(Default)]
+pub struct BatchVerifier<'a> {
+    ed25519_messages: Vec<&'a [u8]>,
+    ed25519_signatures: Vec<&'a [u8]>,
+    ed25519_public_keys: Vec<&'a [u8]>,
+    secp256k1_checks: Vec<(&'a [u8], &'a [u8], &'a [u8])>,
+}
+
+impl<'a> BatchVerifier<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stages an ed25519 check to be resolved by [`Self::verify_all`].
+    pub fn queue_ed25519(
+        &mut self,
+        message: &'a [u8],
+        signature: &'a [u8],
+        public_key: &'a [u8],
+    ) -> &mut Self {
+        self.ed25519_messages.push(message);
+        self.ed25519_signatures.push(signature);
+        self.ed25519_public_keys.push(public_key);
+        self
+    }
+
+    /// Stages a secp256k1 check to be resolved by [`Self::verify_all`].
+    pub fn queue_secp256k1(
+        &mut self,
+        message_hash: &'a [u8],
+        signature: &'a [u8],
+        public_key: &'a [u8],
+    ) -> &mut Self {
+        self.secp256k1_checks
+            .push((message_hash, signature, public_key));
+        self
+    }
+
+    /// Resolves all queued checks, grouping the ed25519 ones into a single import call.
+    /// Short-circuits to `Ok(false)` as soon as any group fails verification.
+    pub fn verify_all(&self, api: &impl Api) -> Result<bool, VerificationError> {
+        if !self.ed25519_messages.is_empty()
+            && !api.ed25519_batch_verify(
+                &self.ed25519_messages,
+                &self.ed25519_signatures,
+                &self.ed25519_public_keys,
+            )?
+        {
+            return Ok(false);
+        }
+
+        for (message_hash, signature, public_key) in &self.secp256k1_checks {
+            if !api.secp256k1_verify(message_hash, signature, public_key)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
 /// Takes a pointer to a Region and reads the data into a String.
 /// This is for trusted string sources only.
 unsafe fn consume_string_region_written_by_vm(from: *mut Region) -> String {
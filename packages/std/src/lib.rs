@@ -0,0 +1,3 @@
+mod imports;
+
+pub use imports::*;